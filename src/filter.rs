@@ -0,0 +1,386 @@
+//! recursive-descent parser and evaluator for the `--filter` expression DSL
+//! used to narrow output after word counts are flattened, e.g.
+//! `count > 5 && len >= 3` or `word == "foo*" || !(count == 0)`.
+
+use crate::glob::Pattern;
+use crate::WordPair;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Count,
+    Len,
+    Word,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+    Ne,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Int(i64),
+    Text(String),
+}
+
+/// predicate AST produced by [`Expr::parse`] and evaluated against each
+/// counted word with [`Expr::eval`]
+#[derive(Debug)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Cmp(Field, Op, Value),
+}
+
+#[derive(Debug)]
+pub struct ParseError(String);
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Expr {
+    pub fn parse(input: &str) -> Result<Expr, ParseError> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.parse_or()?;
+        if parser.pos != parser.tokens.len() {
+            return Err(ParseError(format!(
+                "unexpected trailing input after position {}",
+                parser.pos
+            )));
+        }
+        Ok(expr)
+    }
+
+    /// evaluates this expression against a single `(word, count)` pair
+    pub fn eval(&self, pair: &WordPair) -> bool {
+        let (word, count) = pair;
+        match self {
+            Expr::And(lhs, rhs) => lhs.eval(pair) && rhs.eval(pair),
+            Expr::Or(lhs, rhs) => lhs.eval(pair) || rhs.eval(pair),
+            Expr::Not(inner) => !inner.eval(pair),
+            Expr::Cmp(field, op, value) => match (field, value) {
+                (Field::Count, Value::Int(n)) => compare_int(*count as i64, *op, *n),
+                (Field::Len, Value::Int(n)) => compare_int(word.len() as i64, *op, *n),
+                (Field::Word, Value::Text(s)) => compare_word(word, *op, s),
+                _ => false,
+            },
+        }
+    }
+}
+
+fn compare_int(lhs: i64, op: Op, rhs: i64) -> bool {
+    match op {
+        Op::Gt => lhs > rhs,
+        Op::Ge => lhs >= rhs,
+        Op::Lt => lhs < rhs,
+        Op::Le => lhs <= rhs,
+        Op::Eq => lhs == rhs,
+        Op::Ne => lhs != rhs,
+    }
+}
+
+fn compare_word(word: &str, op: Op, rhs: &str) -> bool {
+    let is_match = if rhs.contains(['*', '?', '[']) {
+        Pattern::new(rhs).matches_name(word)
+    } else {
+        word == rhs
+    };
+    match op {
+        Op::Eq => is_match,
+        Op::Ne => !is_match,
+        _ => false,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Op(Op),
+    Field(Field),
+    Int(i64),
+    Text(String),
+}
+
+/// characters that terminate a bare (unquoted) word/glob token
+const TOKEN_BOUNDARY: &str = "()!&|><=\"";
+
+fn tokenize(input: &str) -> Result<Vec<Token>, ParseError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        match c {
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '&' if chars.get(i + 1) == Some(&'&') => {
+                tokens.push(Token::And);
+                i += 2;
+            }
+            '|' if chars.get(i + 1) == Some(&'|') => {
+                tokens.push(Token::Or);
+                i += 2;
+            }
+            '!' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Op(Op::Ne));
+                i += 2;
+            }
+            '!' => {
+                tokens.push(Token::Not);
+                i += 1;
+            }
+            '=' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Op(Op::Eq));
+                i += 2;
+            }
+            '>' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Op(Op::Ge));
+                i += 2;
+            }
+            '>' => {
+                tokens.push(Token::Op(Op::Gt));
+                i += 1;
+            }
+            '<' if chars.get(i + 1) == Some(&'=') => {
+                tokens.push(Token::Op(Op::Le));
+                i += 2;
+            }
+            '<' => {
+                tokens.push(Token::Op(Op::Lt));
+                i += 1;
+            }
+            '"' => {
+                let mut s = String::new();
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' && i + 1 < chars.len() {
+                        i += 1;
+                    }
+                    s.push(chars[i]);
+                    i += 1;
+                }
+                if i >= chars.len() {
+                    return Err(ParseError("unterminated string literal".to_string()));
+                }
+                i += 1;
+                tokens.push(Token::Text(s));
+            }
+            _ => {
+                let start = i;
+                while i < chars.len() && !chars[i].is_whitespace() && !TOKEN_BOUNDARY.contains(chars[i]) {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                if word.is_empty() {
+                    return Err(ParseError(format!("unexpected character '{}'", c)));
+                }
+                tokens.push(match word.as_str() {
+                    "count" => Token::Field(Field::Count),
+                    "len" => Token::Field(Field::Len),
+                    "word" => Token::Field(Field::Word),
+                    _ => match word.parse::<i64>() {
+                        Ok(n) => Token::Int(n),
+                        Err(_) => Token::Text(word),
+                    },
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn bump(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, ParseError> {
+        let mut lhs = self.parse_and()?;
+        while matches!(self.peek(), Some(Token::Or)) {
+            self.bump();
+            let rhs = self.parse_and()?;
+            lhs = Expr::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, ParseError> {
+        let mut lhs = self.parse_unary()?;
+        while matches!(self.peek(), Some(Token::And)) {
+            self.bump();
+            let rhs = self.parse_unary()?;
+            lhs = Expr::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, ParseError> {
+        if matches!(self.peek(), Some(Token::Not)) {
+            self.bump();
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, ParseError> {
+        match self.bump() {
+            Some(Token::LParen) => {
+                let expr = self.parse_or()?;
+                match self.bump() {
+                    Some(Token::RParen) => Ok(expr),
+                    other => Err(ParseError(format!("expected ')', found {:?}", other))),
+                }
+            }
+            Some(Token::Field(field)) => self.parse_comparison(field),
+            other => Err(ParseError(format!(
+                "expected 'count', 'len', 'word' or '(', found {:?}",
+                other
+            ))),
+        }
+    }
+
+    fn parse_comparison(&mut self, field: Field) -> Result<Expr, ParseError> {
+        let op = match self.bump() {
+            Some(Token::Op(op)) => op,
+            other => {
+                return Err(ParseError(format!(
+                    "expected a comparison operator after '{:?}', found {:?}",
+                    field, other
+                )))
+            }
+        };
+        let value = match self.bump() {
+            Some(Token::Int(n)) => Value::Int(n),
+            Some(Token::Text(s)) => Value::Text(s),
+            other => {
+                return Err(ParseError(format!(
+                    "expected a value after comparison operator, found {:?}",
+                    other
+                )))
+            }
+        };
+        match (field, &value, op) {
+            (Field::Word, Value::Int(_), _) => Err(ParseError(
+                "'word' comparisons require a string or glob value".to_string(),
+            )),
+            (Field::Word, _, Op::Gt | Op::Ge | Op::Lt | Op::Le) => Err(ParseError(
+                "'word' only supports '==' and '!='".to_string(),
+            )),
+            (Field::Count | Field::Len, Value::Text(_), _) => Err(ParseError(format!(
+                "'{:?}' comparisons require an integer value",
+                field
+            ))),
+            _ => Ok(Expr::Cmp(field, op, value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(word: &str, count: u32) -> WordPair {
+        (word.to_string(), count)
+    }
+
+    #[test]
+    fn simple_comparisons_and_boolean_combinators() {
+        let expr = Expr::parse("count > 5 && len >= 3").unwrap();
+        assert!(expr.eval(&pair("hello", 6)));
+        assert!(!expr.eval(&pair("hi", 6)));
+        assert!(!expr.eval(&pair("hello", 5)));
+
+        let expr = Expr::parse("count < 2 || len == 4").unwrap();
+        assert!(expr.eval(&pair("wxyz", 100)));
+        assert!(expr.eval(&pair("a", 1)));
+        assert!(!expr.eval(&pair("abc", 100)));
+    }
+
+    #[test]
+    fn not_and_parens_override_default_precedence() {
+        let expr = Expr::parse("!(count == 0)").unwrap();
+        assert!(expr.eval(&pair("x", 1)));
+        assert!(!expr.eval(&pair("x", 0)));
+
+        // && binds tighter than ||, matching the grammar's parse_or/parse_and split
+        let expr = Expr::parse("count == 0 || count == 1 && len == 9").unwrap();
+        assert!(expr.eval(&pair("x", 0)));
+        assert!(!expr.eval(&pair("x", 1)));
+
+        // parentheses override that precedence
+        let expr = Expr::parse("(count == 0 || count == 1) && len == 9").unwrap();
+        assert!(!expr.eval(&pair("x", 0)));
+        assert!(expr.eval(&pair("xxxxxxxxx", 1)));
+    }
+
+    #[test]
+    fn word_field_supports_literal_and_glob_equality() {
+        let expr = Expr::parse("word == \"hello\"").unwrap();
+        assert!(expr.eval(&pair("hello", 1)));
+        assert!(!expr.eval(&pair("hellop", 1)));
+
+        let expr = Expr::parse("word == foo*").unwrap();
+        assert!(expr.eval(&pair("foobar", 1)));
+        assert!(!expr.eval(&pair("barfoo", 1)));
+
+        let expr = Expr::parse("word != \"hello\"").unwrap();
+        assert!(!expr.eval(&pair("hello", 1)));
+        assert!(expr.eval(&pair("goodbye", 1)));
+    }
+
+    #[test]
+    fn rejects_incomplete_expression_instead_of_panicking() {
+        assert!(Expr::parse("count >").is_err());
+        assert!(Expr::parse("count > 5 &&").is_err());
+        assert!(Expr::parse("(count > 5").is_err());
+        assert!(Expr::parse("").is_err());
+    }
+
+    #[test]
+    fn rejects_unterminated_string_literal() {
+        assert!(Expr::parse("word == \"unterminated").is_err());
+    }
+
+    #[test]
+    fn rejects_type_mismatched_comparisons() {
+        assert!(Expr::parse("word > 5").is_err());
+        assert!(Expr::parse("count == \"five\"").is_err());
+        assert!(Expr::parse("len != \"three\"").is_err());
+    }
+}
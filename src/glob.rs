@@ -0,0 +1,364 @@
+//! self-contained glob matcher for `-p`/`--path` and `--ignore` input selection.
+//!
+//! supports `?`, `*`, `[...]`/`[!...]` character classes within a single path
+//! component, and `**` for matching zero or more whole path components.
+
+use std::path::{Path, PathBuf};
+
+/// a parsed glob pattern, split into path components.
+///
+/// a trailing `/` in the source pattern marks the pattern as directory-only
+/// (`requires_dir`) and is stripped from the component list.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    raw: String,
+    components: Vec<String>,
+    requires_dir: bool,
+}
+
+impl Pattern {
+    pub fn new(raw: &str) -> Self {
+        let mut parts: Vec<String> = raw.split('/').map(|s| s.to_string()).collect();
+        let requires_dir = parts.len() > 1 && parts.last().is_some_and(|s| s.is_empty());
+        if requires_dir {
+            parts.pop();
+        }
+        let components = parts.into_iter().filter(|p| !p.is_empty()).collect();
+        Pattern {
+            raw: raw.to_string(),
+            components,
+            requires_dir,
+        }
+    }
+
+    /// true if the raw pattern contains any glob metacharacter
+    pub fn has_meta(&self) -> bool {
+        self.raw.contains(['*', '?', '['])
+    }
+
+    /// matches a single path component (e.g. a file or directory name)
+    /// against this pattern. a pattern of more than one component never
+    /// matches, and the directory-only flag set by a trailing `/` is not
+    /// checked.
+    pub fn matches_name(&self, name: &str) -> bool {
+        match self.components.as_slice() {
+            [only] => match_component(only, name),
+            _ => false,
+        }
+    }
+
+    /// matches a full path against this pattern, component by component,
+    /// with `**` allowed to span zero or more components.
+    pub fn matches_path(&self, path: &Path) -> bool {
+        if self.requires_dir && !path.is_dir() {
+            return false;
+        }
+        let path_str = path.to_string_lossy();
+        // a leading "./" (as produced when `expand` walks from a "." literal
+        // prefix) is not a real path component and must not shift alignment
+        // against the pattern's components
+        let text_components: Vec<&str> = path_str
+            .split('/')
+            .filter(|s| !s.is_empty() && *s != ".")
+            .collect();
+        let pat_components: Vec<&str> = self.components.iter().map(String::as_str).collect();
+        match_components(&pat_components, &text_components)
+    }
+
+    /// walks the filesystem starting from the pattern's non-glob prefix and
+    /// returns the paths that match. a matching directory is returned as a
+    /// single entry; the paths inside it are not searched or returned.
+    pub fn expand(&self) -> Vec<PathBuf> {
+        let mut out = Vec::new();
+        let prefix = self.literal_prefix();
+        walk(&prefix, self, &mut out);
+        out
+    }
+
+    /// the longest leading run of pattern components containing no glob
+    /// metacharacters and no `**`, used as the starting point for `expand`.
+    fn literal_prefix(&self) -> PathBuf {
+        let mut prefix = PathBuf::new();
+        if self.raw.starts_with('/') {
+            prefix.push("/");
+        }
+        for comp in &self.components {
+            if comp == "**" || comp.contains(['*', '?', '[']) {
+                break;
+            }
+            prefix.push(comp);
+        }
+        if prefix.as_os_str().is_empty() {
+            PathBuf::from(".")
+        } else {
+            prefix
+        }
+    }
+}
+
+fn walk(dir: &Path, pattern: &Pattern, out: &mut Vec<PathBuf>) {
+    if !dir.is_dir() {
+        if dir.exists() && pattern.matches_path(dir) {
+            out.push(dir.to_path_buf());
+        }
+        return;
+    }
+    let entries = match dir.read_dir() {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let matched = pattern.matches_path(&path);
+        if matched {
+            out.push(path.clone());
+        }
+        // a directory that already matched is handed to the caller as a
+        // single unit (it will be read via the usual file/dir
+        // dispatch); descending into it here would also independently match
+        // its contents against a trailing `**`, double-counting them
+        if path.is_dir() && !matched {
+            walk(&path, pattern, out);
+        }
+    }
+}
+
+/// matches a sequence of pattern components against a sequence of path
+/// components, backtracking over `**` the same way `match_component`
+/// backtracks over `*` within a component.
+fn match_components(pat: &[&str], txt: &[&str]) -> bool {
+    let (mut pi, mut ti) = (0usize, 0usize);
+    let mut star_idx: Option<usize> = None;
+    let mut match_idx = 0usize;
+
+    loop {
+        if ti < txt.len() {
+            if pi < pat.len() && pat[pi] == "**" {
+                star_idx = Some(pi);
+                match_idx = ti;
+                pi += 1;
+                continue;
+            }
+            if pi < pat.len() && match_component(pat[pi], txt[ti]) {
+                pi += 1;
+                ti += 1;
+                continue;
+            }
+            if let Some(star) = star_idx {
+                pi = star + 1;
+                match_idx += 1;
+                ti = match_idx;
+                continue;
+            }
+            return false;
+        } else {
+            while pi < pat.len() && pat[pi] == "**" {
+                pi += 1;
+            }
+            return pi == pat.len();
+        }
+    }
+}
+
+/// matches a single path component against a single pattern component,
+/// supporting `?`, `*` and `[...]`/`[!...]` classes with two-pointer
+/// backtracking on `*`.
+fn match_component(pattern: &str, text: &str) -> bool {
+    let pat: Vec<char> = pattern.chars().collect();
+    let txt: Vec<char> = text.chars().collect();
+    let (mut pi, mut ti) = (0usize, 0usize);
+    let mut star_idx: Option<usize> = None;
+    let mut match_idx = 0usize;
+
+    loop {
+        if ti < txt.len() {
+            if pi < pat.len() && pat[pi] == '?' {
+                pi += 1;
+                ti += 1;
+                continue;
+            }
+            if pi < pat.len() && pat[pi] == '*' {
+                star_idx = Some(pi);
+                match_idx = ti;
+                pi += 1;
+                continue;
+            }
+            if pi < pat.len() && pat[pi] == '[' {
+                if let Some((matched, next_pi)) = match_class(&pat, pi, txt[ti]) {
+                    if matched {
+                        pi = next_pi;
+                        ti += 1;
+                        continue;
+                    }
+                } else if txt[ti] == '[' {
+                    // no closing ']' found: treat '[' as a literal character
+                    pi += 1;
+                    ti += 1;
+                    continue;
+                }
+            } else if pi < pat.len() && pat[pi] == txt[ti] {
+                pi += 1;
+                ti += 1;
+                continue;
+            }
+            if let Some(star) = star_idx {
+                pi = star + 1;
+                match_idx += 1;
+                ti = match_idx;
+                continue;
+            }
+            return false;
+        } else {
+            while pi < pat.len() && pat[pi] == '*' {
+                pi += 1;
+            }
+            return pi == pat.len();
+        }
+    }
+}
+
+/// parses a `[...]`/`[!...]` class starting at `pat[start] == '['` and tests
+/// `c` against it. returns `None` if no closing `]` exists, in which case the
+/// caller should treat `[` as a literal character.
+fn match_class(pat: &[char], start: usize, c: char) -> Option<(bool, usize)> {
+    let mut i = start + 1;
+    let negate = i < pat.len() && (pat[i] == '!' || pat[i] == '^');
+    if negate {
+        i += 1;
+    }
+    let class_start = i;
+    // a ']' immediately after '[' (or '[!') is a literal member of the class
+    let mut j = class_start;
+    if j < pat.len() && pat[j] == ']' {
+        j += 1;
+    }
+    while j < pat.len() && pat[j] != ']' {
+        j += 1;
+    }
+    if j >= pat.len() {
+        return None;
+    }
+    let class_end = j;
+
+    let mut matched = false;
+    let mut k = class_start;
+    while k < class_end {
+        if k + 2 < class_end && pat[k + 1] == '-' {
+            let (lo, hi) = (pat[k], pat[k + 2]);
+            if c >= lo && c <= hi {
+                matched = true;
+            }
+            k += 3;
+        } else {
+            if pat[k] == c {
+                matched = true;
+            }
+            k += 1;
+        }
+    }
+    if negate {
+        matched = !matched;
+    }
+    Some((matched, class_end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+    /// creates a fresh, uniquely-named directory under the OS temp dir
+    fn temp_dir(label: &str) -> PathBuf {
+        let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+        let pid = std::process::id();
+        let dir = std::env::temp_dir().join(format!("wc_glob_test_{}_{}_{}", pid, label, id));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// restores the process's current directory on drop, even on panic
+    struct CwdGuard(PathBuf);
+
+    impl CwdGuard {
+        fn enter(dir: &Path) -> Self {
+            let previous = std::env::current_dir().unwrap();
+            std::env::set_current_dir(dir).unwrap();
+            CwdGuard(previous)
+        }
+    }
+
+    impl Drop for CwdGuard {
+        fn drop(&mut self) {
+            let _ = std::env::set_current_dir(&self.0);
+        }
+    }
+
+    #[test]
+    fn matches_path_ignores_a_leading_dot_component() {
+        // regression: literal_prefix() falls back to "." when a pattern has
+        // no literal leading component, so expand() builds candidate paths
+        // like "./foo.txt" -- matches_path must not let that synthetic "."
+        // component shift alignment against the pattern's own components.
+        assert!(Pattern::new("*.txt").matches_path(Path::new("./foo.txt")));
+        assert!(Pattern::new("?.txt").matches_path(Path::new("./a.txt")));
+        assert!(Pattern::new("[ab]*.txt").matches_path(Path::new("./a1.txt")));
+        assert!(!Pattern::new("*.txt").matches_path(Path::new("./foo.rs")));
+    }
+
+    #[test]
+    fn expand_finds_bare_top_level_patterns_from_the_current_directory() {
+        let root = temp_dir("bare_pattern");
+        fs::write(root.join("foo.txt"), "x").unwrap();
+        fs::write(root.join("bar.rs"), "y").unwrap();
+
+        let guard = CwdGuard::enter(&root);
+        let matches = Pattern::new("*.txt").expand();
+
+        assert_eq!(matches.len(), 1);
+        assert!(matches[0].ends_with("foo.txt"));
+
+        drop(guard);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn expand_does_not_double_count_a_directory_matched_by_double_star() {
+        let root = temp_dir("double_star");
+        fs::write(root.join("a.txt"), "hello world hello").unwrap();
+        fs::write(root.join("b.txt"), "foo bar").unwrap();
+        fs::create_dir(root.join("sub")).unwrap();
+        fs::write(root.join("sub").join("c.txt"), "deep text").unwrap();
+
+        let pattern = Pattern::new(&format!("{}/**", root.display()));
+        let matches = pattern.expand();
+
+        // "sub" is itself a valid "**" match and is handed to the caller as
+        // a single unit; its contents must not also appear independently,
+        // or the caller (which reads every matched directory) would
+        // read "c.txt" twice.
+        assert!(matches.contains(&root.join("sub")));
+        assert!(!matches.contains(&root.join("sub").join("c.txt")));
+        assert!(matches.contains(&root.join("a.txt")));
+        assert!(matches.contains(&root.join("b.txt")));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn trailing_slash_pattern_matches_only_directories() {
+        let root = temp_dir("trailing_slash");
+        fs::write(root.join("a.txt"), "x").unwrap();
+        fs::create_dir(root.join("sub")).unwrap();
+
+        let pattern = Pattern::new(&format!("{}/*/", root.display()));
+        let matches = pattern.expand();
+
+        assert_eq!(matches, vec![root.join("sub")]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -1,6 +1,10 @@
 extern crate getopts;
 
+mod filter;
+mod glob;
+
 use getopts::Options;
+use glob::Pattern;
 use std::{
     cmp,
     collections::HashMap,
@@ -11,7 +15,6 @@ use std::{
 };
 
 pub type Format = fn(WordCountVec, Box<dyn Write>) -> Result<(), io::Error>;
-pub type SortBy = fn(&WordPair, &WordPair) -> cmp::Ordering;
 pub type Dictionary = HashMap<String, u32>;
 pub type WordPair = (String, u32);
 pub type WordCountVec = Vec<WordPair>;
@@ -87,7 +90,7 @@ impl ParamsError {
 
     fn sort(s: String) -> Self {
         ParamsError {
-            desc: format!("could not parse 'sort by' option {}. \nvalid values are 'count', 'count-desc', 'alpha' and 'alpha-desc'", s),
+            desc: format!("could not parse 'sort by' option {}. \nvalid values are 'count', 'count-desc', 'alpha', 'alpha-desc', 'length' and 'length-desc'", s),
         }
     }
 
@@ -96,6 +99,21 @@ impl ParamsError {
             desc: "although option -p was provided, no actual path was given.".to_string(),
         }
     }
+
+    fn filter(err: filter::ParseError) -> Self {
+        ParamsError {
+            desc: format!("could not parse --filter expression. {}", err),
+        }
+    }
+
+    fn duplicate(flag: &str) -> Self {
+        ParamsError {
+            desc: format!(
+                "--strict: option '-{}' was given more than once",
+                flag
+            ),
+        }
+    }
 }
 
 impl fmt::Display for ParamsError {
@@ -110,6 +128,18 @@ impl std::error::Error for ParamsError {
     }
 }
 
+/// field output is ordered by; the direction is controlled independently by
+/// `WordCountParams::reverse`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortField {
+    /// number of occurances, ties broken by length then lexicographically
+    Count,
+    /// lexicographic order of the word
+    Alpha,
+    /// word length, ties broken lexicographically
+    Length,
+}
+
 /// holds parameters for WordCount
 #[derive(Default)]
 pub struct WordCountParams {
@@ -118,8 +148,17 @@ pub struct WordCountParams {
     ignore_case: bool,
     /// allows descent into subfolders in given path
     recursive: bool,
+    /// glob patterns of files/directories to exclude when reading a directory
+    ignore: Vec<Pattern>,
+    /// includes dotfiles when reading a directory (off by default)
+    show_hidden: bool,
     /// controls if and how output should be sorted
-    sort_by: Option<SortBy>,
+    sort_by: Option<SortField>,
+    /// reverses whichever field `sort_by` selects
+    reverse: bool,
+    /// retains only words matching this predicate, applied after the
+    /// map is flattened and before sorting
+    filter: Option<filter::Expr>,
 }
 
 /**
@@ -154,6 +193,19 @@ impl WordCount {
             .map_err(|res| WcError::ReadFile(path.display().to_string(), res))
     }
 
+    /// true if `path`'s file name begins with `.` while hidden files are
+    /// disabled, or matches one of the configured `--ignore` patterns
+    fn is_ignored(&self, path: &Path) -> bool {
+        let name = match path.file_name().and_then(|n| n.to_str()) {
+            Some(name) => name,
+            None => return false,
+        };
+        if !self.params.show_hidden && name.starts_with('.') {
+            return true;
+        }
+        self.params.ignore.iter().any(|pattern| pattern.matches_name(name))
+    }
+
     /// counts words in files and / or in a directory provided by path
     fn read_dir(&mut self, path: &Path) -> Result<(), WcError> {
         path.read_dir()
@@ -164,6 +216,9 @@ impl WordCount {
                         .map_err(|err| WcError::ReadFile(path.display().to_string(), err))
                         .and_then(|entry_path| {
                             let path = entry_path.path();
+                            if self.is_ignored(&path) {
+                                return Ok(());
+                            }
                             if path.is_dir() && self.params.recursive {
                                 self.read_dir(&path)
                             } else {
@@ -189,6 +244,21 @@ impl WordCount {
             .map_err(WcError::ReadStdIn)
     }
 
+    /// dispatches a single resolved path to `read_file`/`read_dir`, reporting
+    /// `src` (the original `-p` argument, which may have been a glob
+    /// pattern) in errors
+    fn read_path(&mut self, path: &Path, src: &str) -> Result<(), WcError> {
+        if !path.exists() {
+            Err(WcError::InvalidPath(src.to_string()))
+        } else if path.is_file() {
+            self.read_file(path)
+        } else if path.is_dir() {
+            self.read_dir(path)
+        } else {
+            Err(WcError::NotFileNorDir(src.to_string()))
+        }
+    }
+
     /// collects word counts and returns them as vector of touples where
     /// the first value is a String representation of the word and
     /// the second value is a u32 number of occurances
@@ -199,29 +269,36 @@ impl WordCount {
             }
             Some(paths) => {
                 for src in &paths {
-                    let path = Path::new(&src);
-                    {
-                        if !path.exists() {
-                            Err(WcError::InvalidPath(src.to_string()))
-                        } else if dbg!(path.is_file()) {
-                            self.read_file(path)
-                        } else if dbg!(path.is_dir()) {
-                            self.read_dir(path)
-                        } else {
-                            Err(WcError::NotFileNorDir(src.to_string()))
+                    let pattern = Pattern::new(src);
+                    if pattern.has_meta() {
+                        for path in pattern.expand() {
+                            self.read_path(&path, src)?;
                         }
-                    }?
+                    } else {
+                        self.read_path(Path::new(&src), src)?;
+                    }
                 }
             }
         }
         Ok(self.count())
     }
 
-    /// flattens map to vec (sorted if sort_by provided)
+    /// flattens map to vec (filtered if filter provided, sorted if sort_by provided)
     fn count(self) -> WordCountVec {
         let mut v: WordCountVec = self.map.into_iter().collect();
-        if let Some(sort_by) = self.params.sort_by {
-            v.sort_by(sort_by);
+        if let Some(filter) = self.params.filter.as_ref() {
+            v.retain(|pair| filter.eval(pair));
+        }
+        if let Some(field) = self.params.sort_by {
+            let reverse = self.params.reverse;
+            v.sort_by(|first, second| {
+                let ord = compare_by(field, first, second);
+                if reverse {
+                    ord.reverse()
+                } else {
+                    ord
+                }
+            });
         }
         v
     }
@@ -298,47 +375,34 @@ fn json(items: WordCountVec, mut writer: Box<dyn Write>) -> Result<(), io::Error
     writeln!(*writer, "\t]}}")
 }
 
-/// sort by word count ascending
-fn count_asc(first: &WordPair, second: &WordPair) -> cmp::Ordering {
-    let (first, a) = first;
-    let (second, b) = second;
-    let count = a.cmp(b);
-    if count == cmp::Ordering::Equal {
-        let len = first.len().cmp(&second.len());
-        if len == cmp::Ordering::Equal {
-            first.cmp(second)
-        } else {
-            len
-        }
-    } else {
-        count
+/// ascending comparator for `field`; descending order is obtained by
+/// reversing the result, not by a separate function
+fn compare_by(field: SortField, first: &WordPair, second: &WordPair) -> cmp::Ordering {
+    let (word_a, count_a) = first;
+    let (word_b, count_b) = second;
+    match field {
+        SortField::Count => count_a
+            .cmp(count_b)
+            .then_with(|| word_a.len().cmp(&word_b.len()))
+            .then_with(|| word_a.cmp(word_b)),
+        SortField::Alpha => word_a.cmp(word_b),
+        SortField::Length => word_a.len().cmp(&word_b.len()).then_with(|| word_a.cmp(word_b)),
     }
 }
 
-/// sort by word count descending
-fn count_desc(first: &WordPair, second: &WordPair) -> cmp::Ordering {
-    let (first, a) = first;
-    let (second, b) = second;
-    let ord = b.cmp(a);
-    if ord == cmp::Ordering::Equal {
-        second.cmp(first)
-    } else {
-        ord
+/// resolves a single-value option that may have been supplied more than
+/// once: the last occurrence wins, unless `strict` is set, in which case a
+/// repeated value is a hard error naming the offending flag
+fn resolve_last(
+    matches: &getopts::Matches,
+    flag: &str,
+    strict: bool,
+) -> Result<Option<String>, ParamsError> {
+    let values = matches.opt_strs(flag);
+    if strict && values.len() > 1 {
+        return Err(ParamsError::duplicate(flag));
     }
-}
-
-/// sort alphabetically ascending
-fn alpha_asc(first: &WordPair, second: &WordPair) -> cmp::Ordering {
-    let (first, _) = first;
-    let (second, _) = second;
-    first.cmp(second)
-}
-
-/// sort alphabetically desending
-fn alpha_desc(first: &WordPair, second: &WordPair) -> cmp::Ordering {
-    let (first, _) = first;
-    let (second, _) = second;
-    second.cmp(first)
+    Ok(values.into_iter().last())
 }
 
 fn parse_args(args: Vec<String>) -> ParseArgsResult {
@@ -347,25 +411,44 @@ fn parse_args(args: Vec<String>) -> ParseArgsResult {
         .optflag("h", "help", "print this help")
         .optflag("i", "ignore-case", "ignore case (not case sensitive)")
         .optflag("r", "recursive", "parse subfolders")
-        .optopt(
+        .optflag("", "all", "include hidden (dot) files when reading directories")
+        .optflag("R", "reverse", "reverse whichever field --sortby selects")
+        .optflag(
+            "",
+            "strict",
+            "treat a duplicated -f/-s/-o option as a hard error instead of taking the last value",
+        )
+        .optmulti(
             "p",
             "path",
-            "sets desired path to a file or a folder to parse",
+            "sets desired path to a file or a folder to parse (repeatable)",
             "IN_PATH",
         )
-        .optopt(
+        .optmulti(
+            "",
+            "ignore",
+            "glob pattern(s) of files/directories to skip when reading directories (comma-separable)",
+            "PATTERN",
+        )
+        .optmulti(
             "o",
             "output",
-            "path and file name for output file",
+            "path and file name for output file (last occurrence wins)",
             "OUT_PATH",
         )
-        .optopt(
+        .optmulti(
             "s",
             "sortby",
-            "criteria to sort by",
-            "[count|count-desc|alpha|alpha-desc]",
+            "criteria to sort by (last occurrence wins)",
+            "[count|count-desc|alpha|alpha-desc|length|length-desc]",
         )
-        .optopt("f", "format", "output format", "[json|csv]");
+        .optmulti("f", "format", "output format (last occurrence wins)", "[json|csv]")
+        .optopt(
+            "",
+            "filter",
+            "retain only words matching EXPR, e.g. \"count > 5 && len >= 3\"",
+            "EXPR",
+        );
 
     let matches = match options.parse(&args[1..]) {
         Ok(m) => m,
@@ -376,8 +459,18 @@ fn parse_args(args: Vec<String>) -> ParseArgsResult {
         return Err(ParamsError::help(args[0].clone(), options));
     }
 
+    let strict = matches.opt_present("strict");
     let ignore_case = matches.opt_present("i");
     let recursive = matches.opt_present("r");
+    let show_hidden = matches.opt_present("all");
+    let ignore: Vec<Pattern> = matches
+        .opt_strs("ignore")
+        .iter()
+        .flat_map(|patterns| patterns.split(','))
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .map(Pattern::new)
+        .collect();
     let in_path = if matches.opt_present("p") {
         let t = matches.opt_strs("p");
         if t.is_empty() {  // this should never happen
@@ -387,8 +480,8 @@ fn parse_args(args: Vec<String>) -> ParseArgsResult {
     } else {
         None
     };
-    let out_path = matches.opt_str("o");
-    let out_format = match matches.opt_str("f") {
+    let out_path = resolve_last(&matches, "o", strict)?;
+    let out_format = match resolve_last(&matches, "f", strict)? {
         Some(s) => match s.to_lowercase().as_ref() {
             "json" => json,
             "csv" => csv,
@@ -397,14 +490,24 @@ fn parse_args(args: Vec<String>) -> ParseArgsResult {
         None => plain,
     };
 
-    let sort_by: Option<SortBy> = match matches.opt_str("s") {
+    let (sort_by, desc_alias): (Option<SortField>, bool) = match resolve_last(&matches, "s", strict)? {
         Some(s) => match s.to_lowercase().as_ref() {
-            "count" => Some(count_asc),
-            "count-desc" => Some(count_desc),
-            "alpha" => Some(alpha_asc),
-            "alpha-desc" => Some(alpha_desc),
+            "count" => (Some(SortField::Count), false),
+            "count-desc" => (Some(SortField::Count), true),
+            "alpha" => (Some(SortField::Alpha), false),
+            "alpha-desc" => (Some(SortField::Alpha), true),
+            "length" => (Some(SortField::Length), false),
+            "length-desc" => (Some(SortField::Length), true),
             _ => return Err(ParamsError::sort(s)),
         },
+        None => (None, false),
+    };
+    // a "-desc" suffix and an explicit --reverse both flip the direction,
+    // so "count-desc --reverse" cancels back out to ascending
+    let reverse = matches.opt_present("R") ^ desc_alias;
+
+    let filter = match matches.opt_str("filter") {
+        Some(expr) => Some(filter::Expr::parse(&expr).map_err(ParamsError::filter)?),
         None => None,
     };
 
@@ -412,7 +515,11 @@ fn parse_args(args: Vec<String>) -> ParseArgsResult {
         WordCountParams {
             ignore_case,
             recursive,
+            ignore,
+            show_hidden,
             sort_by,
+            reverse,
+            filter,
         },
         in_path,
         out_path,